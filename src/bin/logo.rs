@@ -0,0 +1,26 @@
+use logo::{write, WriteOptions};
+
+fn main() {
+    let mut file = std::fs::File::create("target/test.png").unwrap();
+    let image_width = 2;
+    let image_height = 2;
+    let image = vec![
+        // R     G     B     A
+        0x00, 0x00, 0x00, 0xff, //
+        0x10, 0x30, 0x50, 0xff, //
+        //
+        0x10, 0x30, 0x50, 0xff, //
+        0x00, 0x00, 0x00, 0x00, //
+    ];
+
+    match write(
+        &mut file,
+        &image,
+        image_width,
+        image_height,
+        &WriteOptions::default(),
+    ) {
+        Ok(_) => println!("Written image!"),
+        Err(e) => println!("Error {:?}", e),
+    }
+}
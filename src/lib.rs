@@ -0,0 +1,1553 @@
+// Everything outside the `std`-gated items below (the Huffman/Adam7 writer)
+// only touches `core`, so a consumer that disables the default-on `std`
+// feature gets a genuinely `no_std`, allocation-free build of this library.
+// The CLI entry point lives in `src/bin/logo.rs`, which always requires
+// `std` and isn't part of this crate's public API.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod sink {
+    //! A minimal byte-sink trait so callers can target either a
+    //! `std::io::Write` or a fixed caller-provided buffer with no heap
+    //! allocation.
+
+    pub trait Sink {
+        type Error;
+        fn write(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+    }
+
+    /// Write into a caller-provided buffer; no heap allocation. Errors with
+    /// `Overflow` once the buffer is full rather than growing it.
+    pub struct SliceSink<'a> {
+        buf: &'a mut [u8],
+        pos: usize,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Overflow;
+
+    impl<'a> SliceSink<'a> {
+        pub fn new(buf: &'a mut [u8]) -> SliceSink<'a> {
+            SliceSink { buf, pos: 0 }
+        }
+
+        pub fn written(&self) -> usize {
+            self.pos
+        }
+    }
+
+    impl<'a> Sink for SliceSink<'a> {
+        type Error = Overflow;
+
+        fn write(&mut self, data: &[u8]) -> Result<(), Overflow> {
+            let end = self.pos + data.len();
+            if end > self.buf.len() {
+                return Err(Overflow);
+            }
+            self.buf[self.pos..end].copy_from_slice(data);
+            self.pos = end;
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<W: ::std::io::Write> Sink for W {
+        type Error = ::std::io::Error;
+
+        fn write(&mut self, data: &[u8]) -> Result<(), ::std::io::Error> {
+            ::std::io::Write::write_all(self, data)
+        }
+    }
+}
+use sink::Sink;
+
+fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
+    let a = a as i32;
+    let b = b as i32;
+    let c = c as i32;
+    let p = a + b - c;
+    let pa = (p - a).abs();
+    let pb = (p - b).abs();
+    let pc = (p - c).abs();
+    if pa <= pb && pa <= pc {
+        a as u8
+    } else if pb <= pc {
+        b as u8
+    } else {
+        c as u8
+    }
+}
+
+mod crc32 {
+    // https://github.com/ledbettj/crc32/blob/master/rust/src/crc32.rs
+    pub struct Crc32 {
+        table: [u32; 256],
+        value: u32,
+    }
+
+    const CRC32_INITIAL: u32 = 0xedb88320;
+
+    impl Crc32 {
+        pub fn new() -> Crc32 {
+            let mut c = Crc32 {
+                table: [0; 256],
+                value: 0xffffffff,
+            };
+            for i in 0..256 {
+                let mut v = i as u32;
+                for _ in 0..8 {
+                    v = if v & 1 != 0 {
+                        CRC32_INITIAL ^ (v >> 1)
+                    } else {
+                        v >> 1
+                    }
+                }
+                c.table[i] = v;
+            }
+            c
+        }
+
+        pub fn start(&mut self) {
+            self.value = 0xffffffff;
+        }
+
+        pub fn update(&mut self, buf: &[u8]) {
+            for &i in buf {
+                self.value =
+                    self.table[((self.value ^ (i as u32)) & 0xff) as usize] ^ (self.value >> 8);
+            }
+        }
+
+        pub fn finalize(&mut self) -> u32 {
+            self.value ^ 0xffffffff_u32
+        }
+
+        #[allow(dead_code)]
+        pub fn crc(&mut self, buf: &[u8]) -> u32 {
+            self.start();
+            self.update(buf);
+            self.finalize()
+        }
+    }
+}
+
+mod adler32 {
+    // https://en.wikipedia.org/wiki/Adler-32
+
+    pub struct Adler32 {
+        a: u32,
+        b: u32,
+    }
+
+    const MOD_ADLER: u32 = 65521;
+
+    impl Adler32 {
+        pub fn new() -> Adler32 {
+            Adler32 { a: 1, b: 0 }
+        }
+
+        pub fn start(&mut self) {
+            self.a = 1;
+            self.b = 0;
+        }
+
+        pub fn update(&mut self, buf: &[u8]) {
+            for &i in buf {
+                self.a = (self.a + i as u32) % MOD_ADLER;
+                self.b = (self.a + self.b) % MOD_ADLER;
+            }
+        }
+
+        pub fn finalize(&self) -> u32 {
+            (self.b << 16) | self.a
+        }
+
+        #[allow(dead_code)]
+        pub fn crc(&mut self, buf: &[u8]) -> u32 {
+            self.start();
+            self.update(buf);
+            self.finalize()
+        }
+    }
+}
+
+// big endian
+#[inline]
+fn u32_to_u8_be(v: u32) -> [u8; 4] {
+    [(v >> 24) as u8, (v >> 16) as u8, (v >> 8) as u8, v as u8]
+}
+
+/// How hard the DEFLATE matcher should look for back-references before
+/// settling on one. Higher levels walk further down the hash chain, which
+/// finds longer matches at the cost of encoding time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionLevel {
+    Fast,
+    #[default]
+    Default,
+    Best,
+}
+
+impl CompressionLevel {
+    fn max_chain(self) -> usize {
+        match self {
+            CompressionLevel::Fast => 8,
+            CompressionLevel::Default => 128,
+            CompressionLevel::Best => 4096,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod zlib {
+    use super::adler32;
+    use super::u32_to_u8_be;
+    use super::CompressionLevel;
+
+    const WINDOW_SIZE: usize = 32768;
+    const MIN_MATCH: usize = 3;
+    const MAX_MATCH: usize = 258;
+    const HASH_SIZE: usize = 1 << 15;
+
+    #[inline]
+    fn hash3(data: &[u8], i: usize) -> usize {
+        ((data[i] as usize) ^ ((data[i + 1] as usize) << 5) ^ ((data[i + 2] as usize) << 10))
+            & (HASH_SIZE - 1)
+    }
+
+    enum Token {
+        Literal(u8),
+        Match { len: u16, dist: u16 },
+    }
+
+    // LZ77 over a 32 KB sliding window using a hash-chain match finder: hash
+    // every 3-byte run into `head`, and follow `prev` to walk earlier
+    // positions with the same hash, bounded by `max_chain` steps.
+    fn lz77_parse(data: &[u8], max_chain: usize) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut head = vec![None; HASH_SIZE];
+        let mut prev = vec![None; data.len()];
+
+        let mut i = 0;
+        while i < data.len() {
+            let mut best_len = 0;
+            let mut best_dist = 0;
+
+            if i + MIN_MATCH <= data.len() {
+                let h = hash3(data, i);
+                let mut candidate = head[h];
+                let mut steps = 0;
+                while let Some(pos) = candidate {
+                    if i - pos > WINDOW_SIZE {
+                        break;
+                    }
+                    let max_len = ::std::cmp::min(MAX_MATCH, data.len() - i);
+                    let mut len = 0;
+                    while len < max_len && data[pos + len] == data[i + len] {
+                        len += 1;
+                    }
+                    if len > best_len && len >= MIN_MATCH {
+                        best_len = len;
+                        best_dist = i - pos;
+                    }
+                    steps += 1;
+                    if steps >= max_chain {
+                        break;
+                    }
+                    candidate = prev[pos];
+                }
+            }
+
+            if best_len >= MIN_MATCH {
+                tokens.push(Token::Match {
+                    len: best_len as u16,
+                    dist: best_dist as u16,
+                });
+                let end = ::std::cmp::min(i + best_len, data.len().saturating_sub(MIN_MATCH - 1));
+                let mut j = i;
+                while j < end {
+                    let h = hash3(data, j);
+                    prev[j] = head[h];
+                    head[h] = Some(j);
+                    j += 1;
+                }
+                i += best_len;
+            } else {
+                tokens.push(Token::Literal(data[i]));
+                if i + MIN_MATCH <= data.len() {
+                    let h = hash3(data, i);
+                    prev[i] = head[h];
+                    head[h] = Some(i);
+                }
+                i += 1;
+            }
+        }
+
+        tokens
+    }
+
+    struct BitWriter {
+        out: Vec<u8>,
+        acc: u32,
+        nbits: u32,
+    }
+
+    impl BitWriter {
+        fn new() -> BitWriter {
+            BitWriter {
+                out: Vec::new(),
+                acc: 0,
+                nbits: 0,
+            }
+        }
+
+        // Data elements other than Huffman codes are packed starting with
+        // the least-significant bit of the value.
+        fn write_bits(&mut self, value: u32, bits: u8) {
+            self.acc |= value << self.nbits;
+            self.nbits += bits as u32;
+            while self.nbits >= 8 {
+                self.out.push((self.acc & 0xff) as u8);
+                self.acc >>= 8;
+                self.nbits -= 8;
+            }
+        }
+
+        // Huffman codes are packed starting with the most-significant bit
+        // of the code, so write them bit-reversed into our LSB-first stream.
+        fn write_huffman(&mut self, code: u32, bits: u8) {
+            let mut reversed = 0u32;
+            let mut c = code;
+            for _ in 0..bits {
+                reversed = (reversed << 1) | (c & 1);
+                c >>= 1;
+            }
+            self.write_bits(reversed, bits);
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            if self.nbits > 0 {
+                self.out.push((self.acc & 0xff) as u8);
+            }
+            self.out
+        }
+    }
+
+    // Fixed Huffman literal/length code per RFC 1951 3.2.6.
+    fn fixed_lit_code(sym: u16) -> (u32, u8) {
+        if sym <= 143 {
+            (0x30 + sym as u32, 8)
+        } else if sym <= 255 {
+            (0x190 + (sym - 144) as u32, 9)
+        } else if sym <= 279 {
+            ((sym - 256) as u32, 7)
+        } else {
+            (0xc0 + (sym - 280) as u32, 8)
+        }
+    }
+
+    fn fixed_dist_code(sym: u8) -> (u32, u8) {
+        (sym as u32, 5)
+    }
+
+    // (symbol, base_length, extra_bits) for lengths 3..=258.
+    const LENGTH_TABLE: [(u16, u16, u8); 29] = [
+        (257, 3, 0),
+        (258, 4, 0),
+        (259, 5, 0),
+        (260, 6, 0),
+        (261, 7, 0),
+        (262, 8, 0),
+        (263, 9, 0),
+        (264, 10, 0),
+        (265, 11, 1),
+        (266, 13, 1),
+        (267, 15, 1),
+        (268, 17, 1),
+        (269, 19, 2),
+        (270, 23, 2),
+        (271, 27, 2),
+        (272, 31, 2),
+        (273, 35, 3),
+        (274, 43, 3),
+        (275, 51, 3),
+        (276, 59, 3),
+        (277, 67, 4),
+        (278, 83, 4),
+        (279, 99, 4),
+        (280, 115, 4),
+        (281, 131, 5),
+        (282, 163, 5),
+        (283, 195, 5),
+        (284, 227, 5),
+        (285, 258, 0),
+    ];
+
+    fn length_code(len: u16) -> (u16, u8, u16) {
+        for &(sym, base, extra_bits) in LENGTH_TABLE.iter().rev() {
+            if len >= base {
+                return (sym, extra_bits, len - base);
+            }
+        }
+        unreachable!()
+    }
+
+    // (symbol, base_distance, extra_bits) for distances 1..=32768.
+    const DISTANCE_TABLE: [(u8, u16, u8); 30] = [
+        (0, 1, 0),
+        (1, 2, 0),
+        (2, 3, 0),
+        (3, 4, 0),
+        (4, 5, 1),
+        (5, 7, 1),
+        (6, 9, 2),
+        (7, 13, 2),
+        (8, 17, 3),
+        (9, 25, 3),
+        (10, 33, 4),
+        (11, 49, 4),
+        (12, 65, 5),
+        (13, 97, 5),
+        (14, 129, 6),
+        (15, 193, 6),
+        (16, 257, 7),
+        (17, 385, 7),
+        (18, 513, 8),
+        (19, 769, 8),
+        (20, 1025, 9),
+        (21, 1537, 9),
+        (22, 2049, 10),
+        (23, 3073, 10),
+        (24, 4097, 11),
+        (25, 6145, 11),
+        (26, 8193, 12),
+        (27, 12289, 12),
+        (28, 16385, 13),
+        (29, 24577, 13),
+    ];
+
+    fn distance_code(dist: u16) -> (u8, u8, u16) {
+        for &(sym, base, extra_bits) in DISTANCE_TABLE.iter().rev() {
+            if dist >= base {
+                return (sym, extra_bits, dist - base);
+            }
+        }
+        unreachable!()
+    }
+
+    // Single BTYPE=01 (fixed Huffman) deflate block covering all of `data`.
+    fn deflate_fixed(data: &[u8], level: CompressionLevel) -> Vec<u8> {
+        let tokens = lz77_parse(data, level.max_chain());
+
+        let mut bw = BitWriter::new();
+        bw.write_bits(1, 1); // BFINAL
+        bw.write_bits(0b01, 2); // BTYPE = fixed Huffman
+
+        for token in &tokens {
+            match *token {
+                Token::Literal(byte) => {
+                    let (code, bits) = fixed_lit_code(byte as u16);
+                    bw.write_huffman(code, bits);
+                }
+                Token::Match { len, dist } => {
+                    let (sym, extra_bits, extra) = length_code(len);
+                    let (code, bits) = fixed_lit_code(sym);
+                    bw.write_huffman(code, bits);
+                    if extra_bits > 0 {
+                        bw.write_bits(extra as u32, extra_bits);
+                    }
+
+                    let (dsym, dextra_bits, dextra) = distance_code(dist);
+                    let (dcode, dbits) = fixed_dist_code(dsym);
+                    bw.write_huffman(dcode, dbits);
+                    if dextra_bits > 0 {
+                        bw.write_bits(dextra as u32, dextra_bits);
+                    }
+                }
+            }
+        }
+
+        let (code, bits) = fixed_lit_code(256); // end of block
+        bw.write_huffman(code, bits);
+
+        bw.finish()
+    }
+
+    // Uncompressed (BTYPE=00) blocks, chunked to the 65535-byte stored-block
+    // limit. Used as a fallback when fixed-Huffman coding doesn't pay off.
+    fn stored_blocks(data: &[u8]) -> Vec<u8> {
+        const CHUNK_SIZE: usize = 65530;
+
+        let final_len =
+            // every chunk adds 5 bytes [1:type, 4:size].
+            (5 * {
+                let n = data.len() / CHUNK_SIZE;
+                // include an extra chunk when we don't fit exactly into CHUNK_SIZE
+                n + {usize::from(data.len() != n * CHUNK_SIZE || data.is_empty())}
+            }) +
+            // data
+            data.len()
+        ;
+
+        let mut raw_data = Vec::with_capacity(final_len);
+        let mut pos_curr = 0_usize;
+        loop {
+            let pos_next = ::std::cmp::min(data.len(), pos_curr + CHUNK_SIZE);
+            let chunk_len = (pos_next - pos_curr) as u32;
+            let is_last = pos_next == data.len();
+            raw_data.extend([
+                // type
+                u8::from(is_last),
+                // size
+                (chunk_len & 0xff) as u8,
+                ((chunk_len >> 8) & 0xff) as u8,
+                (0xff - (chunk_len & 0xff)) as u8,
+                (0xff - ((chunk_len >> 8) & 0xff)) as u8,
+            ]);
+
+            raw_data.extend(&data[pos_curr..pos_next]);
+
+            if is_last {
+                break;
+            }
+            pos_curr = pos_next;
+        }
+
+        assert_eq!(final_len, raw_data.len());
+        raw_data
+    }
+
+    pub fn compress(data: &[u8], level: CompressionLevel) -> Vec<u8> {
+        let deflated = deflate_fixed(data, level);
+        let stored = stored_blocks(data);
+        let body = if deflated.len() < stored.len() {
+            deflated
+        } else {
+            stored
+        };
+
+        let mut raw_data = Vec::with_capacity(2 + body.len() + 4);
+        // header
+        raw_data.extend([120, 1]);
+        raw_data.extend(body);
+
+        let mut adler = adler32::Adler32::new();
+        adler.update(data);
+        raw_data.extend(u32_to_u8_be(adler.finalize()));
+
+        raw_data
+    }
+}
+
+#[cfg(feature = "std")]
+mod filter {
+    // PNG scanline filters (section 9 of the spec), picked per row with the
+    // standard minimum-sum-of-absolute-differences heuristic.
+    use super::paeth_predictor;
+
+    fn sum_abs(data: &[u8]) -> u32 {
+        data.iter().map(|&b| (b as i8).unsigned_abs() as u32).sum()
+    }
+
+    // `prev` is the previously written scanline (all zero for the first
+    // row); `bpp` is the number of bytes per pixel.
+    pub fn choose_and_write(out: &mut Vec<u8>, row: &[u8], prev: &[u8], bpp: usize) {
+        let len = row.len();
+        let at = |buf: &[u8], i: usize| -> u8 {
+            if i < bpp {
+                0
+            } else {
+                buf[i - bpp]
+            }
+        };
+
+        let mut none = Vec::with_capacity(len);
+        let mut sub = Vec::with_capacity(len);
+        let mut up = Vec::with_capacity(len);
+        let mut average = Vec::with_capacity(len);
+        let mut paeth = Vec::with_capacity(len);
+
+        for i in 0..len {
+            let x = row[i];
+            let a = at(row, i);
+            let b = prev[i];
+            let c = at(prev, i);
+
+            none.push(x);
+            sub.push(x.wrapping_sub(a));
+            up.push(x.wrapping_sub(b));
+            average.push(x.wrapping_sub(((a as u32 + b as u32) / 2) as u8));
+            paeth.push(x.wrapping_sub(paeth_predictor(a, b, c)));
+        }
+
+        let candidates = [
+            (0u8, &none),
+            (1u8, &sub),
+            (2u8, &up),
+            (3u8, &average),
+            (4u8, &paeth),
+        ];
+        let (tag, best) = candidates
+            .iter()
+            .min_by_key(|(_, filtered)| sum_abs(filtered))
+            .unwrap();
+
+        out.push(*tag);
+        out.extend(best.iter());
+    }
+}
+
+/// PNG color type, following the `image` crate's `save_buffer(..., ColorType)`
+/// model: picks the IHDR color-type code and the per-pixel channel count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorType {
+    Grayscale,
+    Rgb,
+    Indexed,
+    GrayscaleAlpha,
+    Rgba,
+}
+
+impl ColorType {
+    fn code(self) -> u8 {
+        match self {
+            ColorType::Grayscale => 0,
+            ColorType::Rgb => 2,
+            ColorType::Indexed => 3,
+            ColorType::GrayscaleAlpha => 4,
+            ColorType::Rgba => 6,
+        }
+    }
+
+    fn channels(self) -> usize {
+        match self {
+            ColorType::Grayscale => 1,
+            ColorType::Rgb => 3,
+            ColorType::Indexed => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgba => 4,
+        }
+    }
+}
+
+/// A palette for `ColorType::Indexed`, up to 256 RGB entries. `alpha`, if
+/// non-empty, supplies one alpha value per leading palette entry; entries
+/// beyond `alpha.len()` are treated as fully opaque.
+pub struct Palette<'a> {
+    pub colors: &'a [[u8; 3]],
+    pub alpha: &'a [u8],
+}
+
+/// Bits per sample. `ColorType::Indexed` only supports `Eight`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+impl BitDepth {
+    fn bits(self) -> u8 {
+        match self {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 16,
+        }
+    }
+
+    fn bytes(self) -> usize {
+        match self {
+            BitDepth::Eight => 1,
+            BitDepth::Sixteen => 2,
+        }
+    }
+}
+
+// Standard gAMA value for sRGB (1/2.2, scaled by 100000).
+const SRGB_GAMMA: u32 = 45455;
+// Standard cHRM white point and primaries for sRGB, scaled by 100000:
+// white x/y, red x/y, green x/y, blue x/y.
+const SRGB_CHROMATICITIES: [u32; 8] = [31270, 32900, 64000, 33000, 30000, 60000, 15000, 6000];
+
+/// Output format and encoding options for `write`.
+pub struct WriteOptions<'a> {
+    pub color: ColorType,
+    pub palette: Option<&'a Palette<'a>>,
+    pub bit_depth: BitDepth,
+    pub level: CompressionLevel,
+    /// Emits an `sRGB` chunk with this rendering intent (0-3), paired with
+    /// the standard `gAMA`/`cHRM` values.
+    pub srgb_intent: Option<u8>,
+    /// Emits a `pHYs` chunk: (pixels-per-meter-x, pixels-per-meter-y).
+    pub pixels_per_meter: Option<(u32, u32)>,
+    /// Emits one `tEXt` chunk per (keyword, text) pair.
+    pub text: &'a [(&'a str, &'a str)],
+    /// Writes an Adam7 interlaced image instead of a single sequential pass.
+    pub interlaced: bool,
+}
+
+impl<'a> Default for WriteOptions<'a> {
+    fn default() -> WriteOptions<'a> {
+        WriteOptions {
+            color: ColorType::Rgba,
+            palette: None,
+            bit_depth: BitDepth::Eight,
+            level: CompressionLevel::default(),
+            srgb_intent: None,
+            pixels_per_meter: None,
+            text: &[],
+            interlaced: false,
+        }
+    }
+}
+
+// Adam7 interlacing passes: (x0, y0, dx, dy). A non-interlaced image is
+// just the degenerate single pass (0, 0, 1, 1).
+#[cfg(feature = "std")]
+const ADAM7_PASSES: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+#[cfg(feature = "std")]
+fn adam7_pass_dims(width: u32, height: u32, x0: u32, y0: u32, dx: u32, dy: u32) -> (u32, u32) {
+    let rw = if width > x0 {
+        (width - x0).div_ceil(dx)
+    } else {
+        0
+    };
+    let rh = if height > y0 {
+        (height - y0).div_ceil(dy)
+    } else {
+        0
+    };
+    (rw, rh)
+}
+
+// Filters and appends one interlacing pass's scanlines to `raw_data`. `image`
+// is addressed bottom-up (row 0 is the image's last row), matching the
+// non-interlaced packing below. Each pass starts filtering against an
+// all-zero "previous" row, per the PNG spec.
+#[cfg(feature = "std")]
+#[allow(clippy::too_many_arguments)]
+fn emit_pass(
+    raw_data: &mut Vec<u8>,
+    image: &[u8],
+    width: u32,
+    height: u32,
+    bpp: usize,
+    x0: u32,
+    y0: u32,
+    dx: u32,
+    dy: u32,
+) {
+    let (rw, rh) = adam7_pass_dims(width, height, x0, y0, dx, dy);
+    if rw == 0 || rh == 0 {
+        return;
+    }
+
+    let full_row_len = width as usize * bpp;
+    let pass_row_len = rw as usize * bpp;
+    let mut prev_row = vec![0u8; pass_row_len];
+    let mut pass_row = Vec::with_capacity(pass_row_len);
+
+    for row in 0..rh {
+        let png_y = y0 + row * dy;
+        let src_row = (height - 1 - png_y) as usize * full_row_len;
+
+        pass_row.clear();
+        for col in 0..rw {
+            let src_off = src_row + (x0 + col * dx) as usize * bpp;
+            pass_row.extend_from_slice(&image[src_off..src_off + bpp]);
+        }
+
+        filter::choose_and_write(raw_data, &pass_row, &prev_row, bpp);
+        prev_row.copy_from_slice(&pass_row);
+    }
+}
+
+///
+/// Write pixels to PNG, DEFLATE-compressing the pixel data per `options`.
+/// `options.palette` is required for `ColorType::Indexed` and ignored
+/// otherwise. `file` is any byte [`Sink`], not just a `std::io::Write` —
+/// pass a [`sink::SliceSink`] to encode into a caller-provided buffer.
+///
+#[cfg(feature = "std")]
+pub fn write<W: Sink>(
+    file: &mut W,
+    image: &[u8],
+    width: u32,
+    height: u32,
+    options: &WriteOptions,
+) -> Result<(), W::Error> {
+    let color = options.color;
+    let palette = options.palette;
+    let bit_depth = options.bit_depth;
+    let level = options.level;
+
+    let bpp = color.channels() * bit_depth.bytes();
+    assert!(width as usize * height as usize * bpp == image.len());
+    assert!(
+        color != ColorType::Indexed || palette.is_some_and(|p| p.colors.len() <= 256),
+        "ColorType::Indexed requires a palette of up to 256 entries"
+    );
+    assert!(
+        color != ColorType::Indexed || bit_depth == BitDepth::Eight,
+        "ColorType::Indexed only supports an 8-bit depth"
+    );
+
+    fn png_pack<W: Sink>(file: &mut W, png_tag: &[u8; 4], data: &[u8]) -> Result<(), W::Error> {
+        file.write(&u32_to_u8_be(data.len() as u32))?;
+        file.write(png_tag)?;
+        file.write(data)?;
+        {
+            let mut crc = crc32::Crc32::new();
+            crc.start();
+            crc.update(png_tag);
+            crc.update(data);
+            file.write(&u32_to_u8_be(crc.finalize()))?;
+        }
+        Ok(())
+    }
+
+    file.write(b"\x89PNG\r\n\x1a\n")?;
+    {
+        let wb = u32_to_u8_be(width);
+        let hb = u32_to_u8_be(height);
+        let data = [
+            wb[0],
+            wb[1],
+            wb[2],
+            wb[3],
+            hb[0],
+            hb[1],
+            hb[2],
+            hb[3],
+            bit_depth.bits(),
+            color.code(),
+            0,
+            0,
+            u8::from(options.interlaced),
+        ];
+        png_pack(file, b"IHDR", &data)?;
+    }
+
+    if let Some(intent) = options.srgb_intent {
+        png_pack(file, b"sRGB", &[intent])?;
+        png_pack(file, b"gAMA", &u32_to_u8_be(SRGB_GAMMA))?;
+
+        let mut chrm_data = Vec::with_capacity(SRGB_CHROMATICITIES.len() * 4);
+        for v in SRGB_CHROMATICITIES {
+            chrm_data.extend(u32_to_u8_be(v));
+        }
+        png_pack(file, b"cHRM", &chrm_data)?;
+    }
+
+    if let Some((ppu_x, ppu_y)) = options.pixels_per_meter {
+        let mut phys_data = Vec::with_capacity(9);
+        phys_data.extend(u32_to_u8_be(ppu_x));
+        phys_data.extend(u32_to_u8_be(ppu_y));
+        phys_data.push(1); // unit specifier: meter
+        png_pack(file, b"pHYs", &phys_data)?;
+    }
+
+    for (keyword, text) in options.text {
+        let mut text_data = Vec::with_capacity(keyword.len() + 1 + text.len());
+        text_data.extend(keyword.as_bytes());
+        text_data.push(0);
+        text_data.extend(text.as_bytes());
+        png_pack(file, b"tEXt", &text_data)?;
+    }
+
+    if color == ColorType::Indexed {
+        let palette = palette.expect("ColorType::Indexed requires a palette");
+
+        let mut plte_data = Vec::with_capacity(palette.colors.len() * 3);
+        for rgb in palette.colors {
+            plte_data.extend(rgb);
+        }
+        png_pack(file, b"PLTE", &plte_data)?;
+
+        if palette.alpha.iter().any(|&a| a < 255) {
+            png_pack(file, b"tRNS", palette.alpha)?;
+        }
+    }
+
+    {
+        let mut raw_data = Vec::new();
+        if options.interlaced {
+            for &(x0, y0, dx, dy) in &ADAM7_PASSES {
+                emit_pass(&mut raw_data, image, width, height, bpp, x0, y0, dx, dy);
+            }
+        } else {
+            emit_pass(&mut raw_data, image, width, height, bpp, 0, 0, 1, 1);
+        }
+
+        png_pack(file, b"IDAT", &zlib::compress(&raw_data, level))?;
+    }
+
+    png_pack(file, b"IEND", &[])?;
+
+    Ok(())
+}
+
+// Accumulates a CRC-32 over a chunk's tag + data as it's streamed out, so
+// the caller never needs to buffer the chunk body to compute its trailer.
+struct ChunkWriter<'s, S: Sink> {
+    sink: &'s mut S,
+    crc: crc32::Crc32,
+}
+
+fn begin_chunk<'s, S: Sink>(
+    sink: &'s mut S,
+    tag: &[u8; 4],
+    len: u32,
+) -> Result<ChunkWriter<'s, S>, S::Error> {
+    sink.write(&u32_to_u8_be(len))?;
+    sink.write(tag)?;
+    let mut crc = crc32::Crc32::new();
+    crc.start();
+    crc.update(tag);
+    Ok(ChunkWriter { sink, crc })
+}
+
+impl<'s, S: Sink> ChunkWriter<'s, S> {
+    fn write(&mut self, data: &[u8]) -> Result<(), S::Error> {
+        self.crc.update(data);
+        self.sink.write(data)
+    }
+
+    fn finish(mut self) -> Result<(), S::Error> {
+        self.sink.write(&u32_to_u8_be(self.crc.finalize()))
+    }
+}
+
+// Chunked to the 65535-byte stored-block limit, same as the original
+// uncompressed fallback. The length is a closed-form function of the raw
+// byte count, so it can be written into the IDAT chunk header up front —
+// no buffering of the deflate body is needed.
+const STORED_CHUNK_SIZE: usize = 65530;
+
+fn stored_zlib_len(raw_len: usize) -> usize {
+    let n = raw_len / STORED_CHUNK_SIZE;
+    let blocks = n + usize::from(raw_len != n * STORED_CHUNK_SIZE || raw_len == 0);
+    2 /* zlib header */ + 5 * blocks + raw_len + 4 /* adler trailer */
+}
+
+// Streams `raw_len` total bytes (fed via repeated `push` calls) out as a
+// zlib stream of uncompressed (BTYPE=00) deflate blocks.
+struct StoredZlibWriter {
+    remaining_total: usize,
+    remaining_in_block: usize,
+    adler: adler32::Adler32,
+}
+
+impl StoredZlibWriter {
+    fn new<S: Sink>(chunk: &mut ChunkWriter<S>, raw_len: usize) -> Result<StoredZlibWriter, S::Error> {
+        chunk.write(&[120, 1])?; // zlib header
+        if raw_len == 0 {
+            // `push` is only ever called with the image's scanlines, so for
+            // an empty image it's never called at all — but a stored-block
+            // deflate stream still needs exactly one (empty, final) block.
+            chunk.write(&[1, 0, 0, 0xff, 0xff])?;
+        }
+        Ok(StoredZlibWriter {
+            remaining_total: raw_len,
+            remaining_in_block: 0,
+            adler: adler32::Adler32::new(),
+        })
+    }
+
+    fn push<S: Sink>(&mut self, chunk: &mut ChunkWriter<S>, mut data: &[u8]) -> Result<(), S::Error> {
+        while !data.is_empty() {
+            if self.remaining_in_block == 0 {
+                let block_len = ::core::cmp::min(STORED_CHUNK_SIZE, self.remaining_total) as u32;
+                let is_last = block_len as usize == self.remaining_total;
+                chunk.write(&[
+                    u8::from(is_last),
+                    (block_len & 0xff) as u8,
+                    ((block_len >> 8) & 0xff) as u8,
+                    (0xff - (block_len & 0xff)) as u8,
+                    (0xff - ((block_len >> 8) & 0xff)) as u8,
+                ])?;
+                self.remaining_in_block = block_len as usize;
+            }
+
+            let take = ::core::cmp::min(self.remaining_in_block, data.len());
+            let (head, tail) = data.split_at(take);
+            chunk.write(head)?;
+            self.adler.update(head);
+            self.remaining_in_block -= take;
+            self.remaining_total -= take;
+            data = tail;
+        }
+        Ok(())
+    }
+
+    fn finish<S: Sink>(self, chunk: &mut ChunkWriter<S>) -> Result<(), S::Error> {
+        chunk.write(&u32_to_u8_be(self.adler.finalize()))
+    }
+}
+
+fn filtered_byte(tag: u8, x: u8, a: u8, b: u8, c: u8) -> u8 {
+    match tag {
+        0 => x,
+        1 => x.wrapping_sub(a),
+        2 => x.wrapping_sub(b),
+        3 => x.wrapping_sub(((a as u32 + b as u32) / 2) as u8),
+        4 => x.wrapping_sub(paeth_predictor(a, b, c)),
+        _ => unreachable!(),
+    }
+}
+
+fn abs_i8(b: u8) -> u32 {
+    (b as i8).unsigned_abs() as u32
+}
+
+///
+/// Write pixels to PNG into a fixed, caller-provided buffer with no heap
+/// allocation, returning the number of bytes written. Unlike [`write`],
+/// this doesn't do LZ77/Huffman coding — that needs a match-finder scratch
+/// space sized to the image, which isn't available without the heap —
+/// so it always emits the filtered pixel data as uncompressed (stored)
+/// deflate blocks. Adam7 interlacing isn't supported here yet.
+///
+pub fn write_no_alloc(
+    buf: &mut [u8],
+    image: &[u8],
+    width: u32,
+    height: u32,
+    options: &WriteOptions,
+) -> Result<usize, sink::Overflow> {
+    let color = options.color;
+    let palette = options.palette;
+    let bit_depth = options.bit_depth;
+
+    let bpp = color.channels() * bit_depth.bytes();
+    assert!(width as usize * height as usize * bpp == image.len());
+    assert!(
+        color != ColorType::Indexed || palette.is_some_and(|p| p.colors.len() <= 256),
+        "ColorType::Indexed requires a palette of up to 256 entries"
+    );
+    assert!(
+        color != ColorType::Indexed || bit_depth == BitDepth::Eight,
+        "ColorType::Indexed only supports an 8-bit depth"
+    );
+    assert!(
+        !options.interlaced,
+        "write_no_alloc doesn't support Adam7 interlacing yet"
+    );
+
+    let mut sink = sink::SliceSink::new(buf);
+
+    sink.write(b"\x89PNG\r\n\x1a\n")?;
+    {
+        let wb = u32_to_u8_be(width);
+        let hb = u32_to_u8_be(height);
+        let data = [
+            wb[0],
+            wb[1],
+            wb[2],
+            wb[3],
+            hb[0],
+            hb[1],
+            hb[2],
+            hb[3],
+            bit_depth.bits(),
+            color.code(),
+            0,
+            0,
+            0,
+        ];
+        let mut chunk = begin_chunk(&mut sink, b"IHDR", data.len() as u32)?;
+        chunk.write(&data)?;
+        chunk.finish()?;
+    }
+
+    if let Some(intent) = options.srgb_intent {
+        let mut chunk = begin_chunk(&mut sink, b"sRGB", 1)?;
+        chunk.write(&[intent])?;
+        chunk.finish()?;
+
+        let mut chunk = begin_chunk(&mut sink, b"gAMA", 4)?;
+        chunk.write(&u32_to_u8_be(SRGB_GAMMA))?;
+        chunk.finish()?;
+
+        let mut chunk = begin_chunk(&mut sink, b"cHRM", SRGB_CHROMATICITIES.len() as u32 * 4)?;
+        for v in SRGB_CHROMATICITIES {
+            chunk.write(&u32_to_u8_be(v))?;
+        }
+        chunk.finish()?;
+    }
+
+    if let Some((ppu_x, ppu_y)) = options.pixels_per_meter {
+        let mut chunk = begin_chunk(&mut sink, b"pHYs", 9)?;
+        chunk.write(&u32_to_u8_be(ppu_x))?;
+        chunk.write(&u32_to_u8_be(ppu_y))?;
+        chunk.write(&[1])?; // unit specifier: meter
+        chunk.finish()?;
+    }
+
+    for (keyword, text) in options.text {
+        let mut chunk = begin_chunk(
+            &mut sink,
+            b"tEXt",
+            (keyword.len() + 1 + text.len()) as u32,
+        )?;
+        chunk.write(keyword.as_bytes())?;
+        chunk.write(&[0])?;
+        chunk.write(text.as_bytes())?;
+        chunk.finish()?;
+    }
+
+    if color == ColorType::Indexed {
+        let palette = palette.expect("ColorType::Indexed requires a palette");
+
+        let mut chunk = begin_chunk(&mut sink, b"PLTE", palette.colors.len() as u32 * 3)?;
+        for rgb in palette.colors {
+            chunk.write(rgb)?;
+        }
+        chunk.finish()?;
+
+        if palette.alpha.iter().any(|&a| a < 255) {
+            let mut chunk = begin_chunk(&mut sink, b"tRNS", palette.alpha.len() as u32)?;
+            chunk.write(palette.alpha)?;
+            chunk.finish()?;
+        }
+    }
+
+    {
+        let row_len = width as usize * bpp;
+        let raw_len = (row_len + 1) * height as usize;
+
+        let mut chunk = begin_chunk(&mut sink, b"IDAT", stored_zlib_len(raw_len) as u32)?;
+        let mut zlib = StoredZlibWriter::new(&mut chunk, raw_len)?;
+
+        for y in 0..height {
+            let cur_off = (height - 1 - y) as usize * row_len;
+            let has_prev = y > 0;
+            let prev_off = cur_off + row_len;
+
+            let sample = |off: usize, i: usize| -> u8 {
+                if i < bpp {
+                    0
+                } else {
+                    image[off + i - bpp]
+                }
+            };
+
+            let mut sums = [0u32; 5];
+            for i in 0..row_len {
+                let x = image[cur_off + i];
+                let a = sample(cur_off, i);
+                let b = if has_prev { image[prev_off + i] } else { 0 };
+                let c = if has_prev { sample(prev_off, i) } else { 0 };
+                for (tag, sum) in sums.iter_mut().enumerate() {
+                    *sum += abs_i8(filtered_byte(tag as u8, x, a, b, c));
+                }
+            }
+            let best_tag = (0..5u8).min_by_key(|&tag| sums[tag as usize]).unwrap();
+
+            zlib.push(&mut chunk, &[best_tag])?;
+            for i in 0..row_len {
+                let x = image[cur_off + i];
+                let a = sample(cur_off, i);
+                let b = if has_prev { image[prev_off + i] } else { 0 };
+                let c = if has_prev { sample(prev_off, i) } else { 0 };
+                zlib.push(&mut chunk, &[filtered_byte(best_tag, x, a, b, c)])?;
+            }
+        }
+
+        zlib.finish(&mut chunk)?;
+        chunk.finish()?;
+    }
+
+    {
+        let chunk = begin_chunk(&mut sink, b"IEND", 0)?;
+        chunk.finish()?;
+    }
+
+    Ok(sink.written())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use super::sink::{Overflow, Sink, SliceSink};
+    use std::io::Read;
+
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        flate2::read::ZlibDecoder::new(data)
+            .read_to_end(&mut out)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn deflate_roundtrip_repetitive() {
+        let data: Vec<u8> = (0..2000u32).map(|i| (i % 17) as u8).collect();
+        let compressed = zlib::compress(&data, CompressionLevel::Best);
+        assert_eq!(inflate(&compressed), data);
+    }
+
+    #[test]
+    fn deflate_roundtrip_incompressible() {
+        // A simple LCG, not true randomness, just enough to defeat LZ77
+        // matches and exercise the stored-block fallback.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let data: Vec<u8> = (0..5000)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                (state >> 56) as u8
+            })
+            .collect();
+        let compressed = zlib::compress(&data, CompressionLevel::Best);
+        assert_eq!(inflate(&compressed), data);
+    }
+
+    #[test]
+    fn adam7_pass_dims_matches_spec() {
+        assert_eq!(adam7_pass_dims(8, 8, 0, 0, 8, 8), (1, 1));
+        assert_eq!(adam7_pass_dims(8, 8, 4, 0, 8, 8), (1, 1));
+        assert_eq!(adam7_pass_dims(8, 8, 0, 4, 4, 8), (2, 1));
+        assert_eq!(adam7_pass_dims(8, 8, 2, 0, 4, 4), (2, 2));
+        assert_eq!(adam7_pass_dims(8, 8, 0, 2, 2, 4), (4, 2));
+        assert_eq!(adam7_pass_dims(8, 8, 1, 0, 2, 2), (4, 4));
+        assert_eq!(adam7_pass_dims(8, 8, 0, 1, 1, 2), (8, 4));
+
+        // Non-multiple-of-8 dimensions exercise the ceil-division edge cases.
+        assert_eq!(adam7_pass_dims(5, 3, 0, 0, 8, 8), (1, 1));
+        // y0 >= height: no rows, but the width side is computed independently.
+        assert_eq!(adam7_pass_dims(5, 3, 0, 4, 4, 8), (2, 0));
+        // x0 >= width: no columns.
+        assert_eq!(adam7_pass_dims(1, 1, 4, 0, 8, 8), (0, 1));
+    }
+
+    struct Chunk<'a> {
+        tag: [u8; 4],
+        data: &'a [u8],
+    }
+
+    fn parse_chunks(png: &[u8]) -> Vec<Chunk<'_>> {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let mut chunks = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let tag: [u8; 4] = png[pos + 4..pos + 8].try_into().unwrap();
+            let data = &png[pos + 8..pos + 8 + len];
+            let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+
+            let mut check = crc32::Crc32::new();
+            check.start();
+            check.update(&tag);
+            check.update(data);
+            assert_eq!(check.finalize(), crc, "bad CRC for {:?} chunk", tag);
+
+            chunks.push(Chunk { tag, data });
+            pos += 12 + len;
+        }
+        chunks
+    }
+
+    // Mirrors `emit_pass`'s prev-row/current-row bookkeeping, just undoing
+    // the filter instead of choosing and applying one.
+    fn unfilter(raw: &[u8], width: usize, height: usize, bpp: usize) -> Vec<u8> {
+        let row_len = width * bpp;
+        let mut out = Vec::with_capacity(row_len * height);
+        let mut prev = vec![0u8; row_len];
+        let mut pos = 0;
+        for _ in 0..height {
+            let tag = raw[pos];
+            pos += 1;
+            let mut cur = vec![0u8; row_len];
+            for i in 0..row_len {
+                let x = raw[pos + i];
+                let a = if i >= bpp { cur[i - bpp] } else { 0 };
+                let b = prev[i];
+                let c = if i >= bpp { prev[i - bpp] } else { 0 };
+                cur[i] = match tag {
+                    0 => x,
+                    1 => x.wrapping_add(a),
+                    2 => x.wrapping_add(b),
+                    3 => x.wrapping_add(((a as u32 + b as u32) / 2) as u8),
+                    4 => x.wrapping_add(paeth_predictor(a, b, c)),
+                    _ => panic!("unknown filter tag {}", tag),
+                };
+            }
+            pos += row_len;
+            out.extend_from_slice(&cur);
+            prev = cur;
+        }
+        out
+    }
+
+    // `write`'s input image is addressed bottom-up, but PNG scanlines run
+    // top-down; flip to compare a decoded image back against the input.
+    fn flip_rows(data: &[u8], row_len: usize) -> Vec<u8> {
+        data.chunks(row_len).rev().flatten().copied().collect()
+    }
+
+    #[test]
+    fn sixteen_bit_rgba_roundtrips() {
+        let width = 3u32;
+        let height = 2u32;
+        let bpp = 8usize; // 4 channels * 2 bytes
+        let mut image = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..4u32 {
+                    let sample = (y * 1000 + x * 100 + c) as u16;
+                    image.extend_from_slice(&sample.to_be_bytes());
+                }
+            }
+        }
+
+        let options = WriteOptions {
+            color: ColorType::Rgba,
+            bit_depth: BitDepth::Sixteen,
+            ..WriteOptions::default()
+        };
+        let mut out = Vec::new();
+        write(&mut out, &image, width, height, &options).unwrap();
+
+        let chunks = parse_chunks(&out);
+        let ihdr = chunks.iter().find(|c| &c.tag == b"IHDR").unwrap();
+        assert_eq!(ihdr.data[8], 16); // bit depth
+        assert_eq!(ihdr.data[9], 6); // color type: RGBA
+
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        let decompressed = inflate(&idat);
+
+        let raw = unfilter(&decompressed, width as usize, height as usize, bpp);
+        assert_eq!(flip_rows(&raw, width as usize * bpp), image);
+    }
+
+    #[test]
+    fn indexed_palette_roundtrips() {
+        let width = 4u32;
+        let height = 2u32;
+        let palette_colors: [[u8; 3]; 3] = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        let palette_alpha = [255u8, 128, 255];
+        let palette = Palette {
+            colors: &palette_colors,
+            alpha: &palette_alpha,
+        };
+        let image: Vec<u8> = vec![0, 1, 2, 0, 1, 2, 0, 1];
+        assert_eq!(image.len(), width as usize * height as usize);
+
+        let options = WriteOptions {
+            color: ColorType::Indexed,
+            palette: Some(&palette),
+            ..WriteOptions::default()
+        };
+        let mut out = Vec::new();
+        write(&mut out, &image, width, height, &options).unwrap();
+
+        let chunks = parse_chunks(&out);
+        let ihdr = chunks.iter().find(|c| &c.tag == b"IHDR").unwrap();
+        assert_eq!(ihdr.data[9], 3); // color type: indexed
+
+        let plte = chunks.iter().find(|c| &c.tag == b"PLTE").unwrap();
+        assert_eq!(plte.data, [255, 0, 0, 0, 255, 0, 0, 0, 255]);
+
+        let trns = chunks.iter().find(|c| &c.tag == b"tRNS").unwrap();
+        assert_eq!(trns.data, palette_alpha);
+
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        let raw = inflate(&idat);
+
+        let unfiltered = unfilter(&raw, width as usize, height as usize, 1);
+        assert_eq!(flip_rows(&unfiltered, width as usize), image);
+    }
+
+    #[test]
+    fn write_no_alloc_roundtrips() {
+        let width = 3u32;
+        let height = 2u32;
+        let image: Vec<u8> = (0..(width * height * 4) as u16).map(|v| v as u8).collect();
+
+        let mut buf = [0u8; 256];
+        let n = write_no_alloc(&mut buf, &image, width, height, &WriteOptions::default()).unwrap();
+
+        let chunks = parse_chunks(&buf[..n]);
+        let ihdr = chunks.iter().find(|c| &c.tag == b"IHDR").unwrap();
+        assert_eq!(ihdr.data[9], 6); // color type: RGBA
+
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        let raw = inflate(&idat);
+
+        let unfiltered = unfilter(&raw, width as usize, height as usize, 4);
+        assert_eq!(flip_rows(&unfiltered, width as usize * 4), image);
+    }
+
+    #[test]
+    fn write_no_alloc_zero_height_roundtrips() {
+        // Regression test: a zero-height image has no scanlines to push, so
+        // the declared IDAT length must still match what's actually written.
+        let width = 3u32;
+        let height = 0u32;
+        let image: Vec<u8> = Vec::new();
+
+        let mut buf = [0u8; 128];
+        let n = write_no_alloc(&mut buf, &image, width, height, &WriteOptions::default()).unwrap();
+
+        let chunks = parse_chunks(&buf[..n]);
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        assert_eq!(inflate(&idat), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn write_no_alloc_reports_overflow_on_small_buffer() {
+        let width = 3u32;
+        let height = 2u32;
+        let image = vec![0u8; (width * height * 4) as usize];
+
+        let mut buf = [0u8; 10];
+        let err =
+            write_no_alloc(&mut buf, &image, width, height, &WriteOptions::default()).unwrap_err();
+        assert_eq!(err, Overflow);
+    }
+
+    #[test]
+    fn slice_sink_tracks_position_and_overflows() {
+        let mut storage = [0u8; 4];
+        let mut sink = SliceSink::new(&mut storage);
+        sink.write(b"ab").unwrap();
+        assert_eq!(sink.written(), 2);
+        sink.write(b"cd").unwrap();
+        assert_eq!(sink.written(), 4);
+        assert_eq!(sink.write(b"e").unwrap_err(), Overflow);
+    }
+
+    #[test]
+    fn ancillary_chunks_roundtrip() {
+        let width = 2u32;
+        let height = 2u32;
+        let image = vec![
+            0x00, 0x00, 0x00, 0xff, //
+            0x10, 0x30, 0x50, 0xff, //
+            0x10, 0x30, 0x50, 0xff, //
+            0x00, 0x00, 0x00, 0x00, //
+        ];
+
+        let options = WriteOptions {
+            srgb_intent: Some(0),
+            pixels_per_meter: Some((2835, 2835)), // 72 DPI
+            text: &[("Software", "logo")],
+            ..WriteOptions::default()
+        };
+        let mut out = Vec::new();
+        write(&mut out, &image, width, height, &options).unwrap();
+
+        let chunks = parse_chunks(&out);
+
+        let srgb = chunks.iter().find(|c| &c.tag == b"sRGB").unwrap();
+        assert_eq!(srgb.data, [0]);
+
+        let gama = chunks.iter().find(|c| &c.tag == b"gAMA").unwrap();
+        assert_eq!(u32::from_be_bytes(gama.data.try_into().unwrap()), 45455);
+
+        let chrm = chunks.iter().find(|c| &c.tag == b"cHRM").unwrap();
+        assert_eq!(chrm.data.len(), 32);
+
+        let phys = chunks.iter().find(|c| &c.tag == b"pHYs").unwrap();
+        assert_eq!(&phys.data[0..4], &2835u32.to_be_bytes());
+        assert_eq!(&phys.data[4..8], &2835u32.to_be_bytes());
+        assert_eq!(phys.data[8], 1); // unit specifier: meter
+
+        let text = chunks.iter().find(|c| &c.tag == b"tEXt").unwrap();
+        assert_eq!(text.data, b"Software\0logo");
+
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        let raw = inflate(&idat);
+        let unfiltered = unfilter(&raw, width as usize, height as usize, 4);
+        assert_eq!(flip_rows(&unfiltered, width as usize * 4), image);
+    }
+
+    #[test]
+    fn interlaced_roundtrip() {
+        let width = 5u32;
+        let height = 3u32;
+        let bpp = 4usize;
+        let mut image = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                image.extend([(x * 10) as u8, (y * 20) as u8, 7, 255]);
+            }
+        }
+
+        let options = WriteOptions {
+            interlaced: true,
+            ..WriteOptions::default()
+        };
+        let mut out = Vec::new();
+        write(&mut out, &image, width, height, &options).unwrap();
+
+        let chunks = parse_chunks(&out);
+        let ihdr = chunks.iter().find(|c| &c.tag == b"IHDR").unwrap();
+        assert_eq!(ihdr.data[12], 1); // interlace method: Adam7
+
+        let idat: Vec<u8> = chunks
+            .iter()
+            .filter(|c| &c.tag == b"IDAT")
+            .flat_map(|c| c.data.iter().copied())
+            .collect();
+        let raw = inflate(&idat);
+
+        // Reassemble the 7 Adam7 passes back into a full image, mirroring
+        // `emit_pass`'s addressing (bottom-up rows, same sub-lattice offsets).
+        let full_row_len = width as usize * bpp;
+        let mut reconstructed = vec![0u8; full_row_len * height as usize];
+        let mut pos = 0;
+        for &(x0, y0, dx, dy) in &ADAM7_PASSES {
+            let (rw, rh) = adam7_pass_dims(width, height, x0, y0, dx, dy);
+            if rw == 0 || rh == 0 {
+                continue;
+            }
+            let pass_row_len = rw as usize * bpp;
+            let pass_raw_len = (pass_row_len + 1) * rh as usize;
+            let pass_unfiltered =
+                unfilter(&raw[pos..pos + pass_raw_len], rw as usize, rh as usize, bpp);
+            pos += pass_raw_len;
+
+            for row in 0..rh {
+                let png_y = y0 + row * dy;
+                let dst_row = (height - 1 - png_y) as usize * full_row_len;
+                for col in 0..rw {
+                    let dst_off = dst_row + (x0 + col * dx) as usize * bpp;
+                    let src_off = row as usize * pass_row_len + col as usize * bpp;
+                    reconstructed[dst_off..dst_off + bpp]
+                        .copy_from_slice(&pass_unfiltered[src_off..src_off + bpp]);
+                }
+            }
+        }
+
+        assert_eq!(pos, raw.len());
+        assert_eq!(reconstructed, image);
+    }
+}